@@ -195,7 +195,7 @@ impl MassaRpcServer for API<Public> {
             let result = ExecuteReadOnlyResponse {
                 executed_at: result
                     .as_ref()
-                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
+                    .map_or_else(|_| Slot::genesis(), |v| v.out.slot),
                 result: result.as_ref().map_or_else(
                     |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
                     |res| ReadOnlyResult::Ok(res.call_result.clone()),
@@ -283,7 +283,7 @@ impl MassaRpcServer for API<Public> {
             let result = ExecuteReadOnlyResponse {
                 executed_at: result
                     .as_ref()
-                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
+                    .map_or_else(|_| Slot::genesis(), |v| v.out.slot),
                 result: result.as_ref().map_or_else(
                     |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
                     |res| ReadOnlyResult::Ok(res.call_result.clone()),
@@ -370,7 +370,7 @@ impl MassaRpcServer for API<Public> {
         );
 
         let next_slot_result = last_slot
-            .unwrap_or_else(|| Slot::new(0, 0))
+            .unwrap_or_else(Slot::genesis)
             .get_next_slot(api_settings.thread_count);
 
         let next_slot = match next_slot_result {
@@ -390,7 +390,7 @@ impl MassaRpcServer for API<Public> {
             .collect::<BTreeMap<_, _>>();
 
         let current_cycle = last_slot
-            .unwrap_or_else(|| Slot::new(0, 0))
+            .unwrap_or_else(Slot::genesis)
             .get_cycle(api_settings.periods_per_cycle);
 
         let cycle_duration = match api_settings.t0.checked_mul(api_settings.periods_per_cycle) {
@@ -831,7 +831,7 @@ impl MassaRpcServer for API<Public> {
                 self.0.api_settings.genesis_timestamp,
             )
             .expect("could not get latest current slot")
-            .unwrap_or_else(|| Slot::new(0, 0));
+            .unwrap_or_else(Slot::genesis);
             let slot_end = Slot::new(
                 cur_slot
                     .period
@@ -1006,6 +1006,10 @@ impl MassaRpcServer for API<Public> {
         &self,
         filter: EventFilter,
     ) -> RpcResult<Vec<SCOutputEvent>> {
+        let filter = match filter.require_some() {
+            Ok(filter) => filter,
+            Err(e) => return Err(ApiError::ModelsError(e).into()),
+        };
         let events = self
             .0
             .execution_controller
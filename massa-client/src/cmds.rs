@@ -651,6 +651,10 @@ impl Command {
                     original_operation_id: parse_key_value(&p, p_list[4])?,
                     is_final: parse_key_value(&p, p_list[5])?,
                     is_error: parse_key_value(&p, p_list[6])?,
+                    triggered_by: None,
+                    min_call_depth: None,
+                    // no parameter means that the user asks for every event
+                    match_all: p.is_empty(),
                 };
                 match client.public.get_filtered_sc_output_event(filter).await {
                     Ok(events) => Ok(Box::new(events)),
@@ -1388,7 +1392,7 @@ async fn send_operation(
     .config;
 
     let slot = get_current_latest_block_slot(cfg.thread_count, cfg.t0, cfg.genesis_timestamp)?
-        .unwrap_or_else(|| Slot::new(0, 0));
+        .unwrap_or_else(Slot::genesis);
     let mut expire_period = slot.period + cfg.operation_validity_periods;
     if slot.thread >= addr.get_thread(cfg.thread_count) {
         expire_period += 1;
@@ -3,9 +3,19 @@
 //! This module represents an event store allowing to store, search and retrieve
 //! a config-limited number of execution-generated events
 
+use massa_models::address::Address;
+use massa_models::block_id::BlockId;
+use massa_models::error::ModelsError;
 use massa_models::execution::EventFilter;
+use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
-use std::collections::VecDeque;
+use massa_models::prehash::{PreHashMap, PreHashSet};
+use massa_models::slot::Slot;
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use schnellru::{ByLength, LruMap};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::sync::Arc;
 
 /// Store for events emitted by smart contracts
 #[derive(Default, Debug, Clone)]
@@ -17,6 +27,31 @@ impl EventStore {
         self.0.push_back(event);
     }
 
+    /// Insert an event, replacing the stored event with the same slot and index in slot if any.
+    /// Returns whether an event was replaced.
+    pub fn upsert(&mut self, event: SCOutputEvent) -> bool {
+        let existing = self.0.iter_mut().find(|x| {
+            x.context.slot == event.context.slot
+                && x.context.index_in_slot == event.context.index_in_slot
+        });
+        match existing {
+            Some(existing) => {
+                *existing = event;
+                true
+            }
+            None => {
+                self.0.push_back(event);
+                false
+            }
+        }
+    }
+
+    /// Reserve capacity for at least `additional` more events.
+    /// This is only a hint to avoid reallocations when pushing many events.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
     /// Take the event store
     pub fn take(&mut self) -> VecDeque<SCOutputEvent> {
         std::mem::take(&mut self.0)
@@ -34,6 +69,19 @@ impl EventStore {
         }
     }
 
+    /// Fill ratio of the store relative to `max_events`, between 0.0 and 1.0
+    pub fn pressure(&self, max_events: usize) -> f32 {
+        if max_events == 0 {
+            return 1.0;
+        }
+        (self.0.len() as f32 / max_events as f32).min(1.0)
+    }
+
+    /// Check whether the fill ratio of the store relative to `max_events` reached `threshold`
+    pub fn is_overloaded(&self, max_events: usize, threshold: f32) -> bool {
+        self.pressure(max_events) >= threshold
+    }
+
     /// Extend the event store with another store
     pub fn extend(&mut self, other: EventStore) {
         self.0.extend(other.0);
@@ -46,63 +94,403 @@ impl EventStore {
         }
     }
 
-    /// Get events optionally filtered by:
-    /// * start slot
-    /// * end slot
-    /// * emitter address
-    /// * original caller address
-    /// * operation id
-    /// * is final
+    /// Count the events of the store per slot, ordered by slot.
+    /// Slots without any event are absent from the returned map.
+    pub fn events_per_slot(&self) -> BTreeMap<Slot, usize> {
+        let mut counts = BTreeMap::new();
+        for event in self.0.iter() {
+            *counts.entry(event.context.slot).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Get the distinct addresses (see `SCOutputEvent::emitter`) that emitted events at a slot
+    pub fn emitters_at_slot(&self, slot: Slot) -> PreHashSet<Address> {
+        self.0
+            .iter()
+            .filter(|x| x.context.slot == slot)
+            .filter_map(|x| x.emitter().copied())
+            .collect()
+    }
+
+    /// Get the events of an operation in emission order (slot, then index in slot)
+    pub fn events_for_operation_ordered(&self, op: &OperationId) -> Vec<SCOutputEvent> {
+        let mut events: Vec<SCOutputEvent> = self
+            .0
+            .iter()
+            .filter(|x| x.context.origin_operation_id.as_ref() == Some(op))
+            .cloned()
+            .collect();
+        events.sort_by_key(|x| (x.context.slot, x.context.index_in_slot));
+        events
+    }
+
+    /// Get the most recent event (highest slot, then highest index in slot) of each emitter
+    pub fn latest_per_emitter(&self) -> PreHashMap<Address, SCOutputEvent> {
+        let mut latest: PreHashMap<Address, SCOutputEvent> = PreHashMap::default();
+        for event in self.0.iter() {
+            let Some(emitter) = event.emitter() else {
+                continue;
+            };
+            let is_newer = latest.get(emitter).map_or(true, |current| {
+                (event.context.slot, event.context.index_in_slot)
+                    > (current.context.slot, current.context.index_in_slot)
+            });
+            if is_newer {
+                latest.insert(*emitter, event.clone());
+            }
+        }
+        latest
+    }
+
+    /// Build the tree of the calls that emitted events at the given slot
+    pub fn call_tree_at_slot(&self, slot: Slot) -> CallTree {
+        let mut tree = CallTree::default();
+        for event in self.0.iter().filter(|x| x.context.slot == slot) {
+            tree.insert(event.clone());
+        }
+        tree
+    }
+
+    /// Get the events matching the filter, in store order.
+    /// See `EventFilter::matches` for the predicates that are applied.
     pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
+        self.0
+            .iter()
+            .filter(|x| filter.matches(x))
+            .cloned()
+            .collect()
+    }
+
+    /// Get every `every_nth` event matching the filter, in store order,
+    /// starting with the first matching event. `every_nth == 0` is treated as 1.
+    pub fn sample(&self, filter: &EventFilter, every_nth: usize) -> Vec<SCOutputEvent> {
+        self.0
+            .iter()
+            .filter(|x| filter.matches(x))
+            .step_by(every_nth.max(1))
+            .cloned()
+            .collect()
+    }
+
+    /// Remove the events matching the given filter from the store and return them in store order
+    pub fn drain_filtered(&mut self, filter: &EventFilter) -> Vec<SCOutputEvent> {
+        let (drained, kept): (Vec<_>, VecDeque<_>) = std::mem::take(&mut self.0)
+            .into_iter()
+            .partition(|x| filter.matches(x));
+        self.0 = kept;
+        drained
+    }
+
+    /// Get the events matching the filter that were emitted in a block for which `is_final_block` returns true.
+    /// Events emitted outside of any block are excluded.
+    ///
+    /// This relies on the caller's view of block finality (e.g. consensus)
+    /// rather than on the `is_final` flag of the events.
+    pub fn get_filtered_sc_output_events_in_final_blocks<F>(
+        &self,
+        filter: &EventFilter,
+        is_final_block: F,
+    ) -> VecDeque<SCOutputEvent>
+    where
+        F: Fn(&BlockId) -> bool,
+    {
         self.0
             .iter()
             .filter(|x| {
-                if let Some(start) = filter.start {
-                    if x.context.slot < start {
-                        return false;
-                    }
-                }
-                if let Some(end) = filter.end {
-                    if x.context.slot >= end {
-                        return false;
-                    }
-                }
-                if let Some(is_final) = filter.is_final {
-                    if x.context.is_final != is_final {
-                        return false;
-                    }
-                }
-                if let Some(is_error) = filter.is_error {
-                    if x.context.is_error != is_error {
-                        return false;
-                    }
-                }
-                match (filter.emitter_address, x.context.call_stack.front()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_caller_address, x.context.call_stack.back()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_operation_id, x.context.origin_operation_id) {
-                    (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                true
+                x.context.block.as_ref().map_or(false, &is_final_block) && filter.matches(x)
             })
             .cloned()
             .collect()
     }
+
+    /// Write the events matching the filter as newline-delimited JSON, one event per line.
+    /// Returns the number of events written.
+    pub fn write_ndjson<W: Write>(
+        &self,
+        filter: &EventFilter,
+        w: &mut W,
+    ) -> Result<usize, ModelsError> {
+        let mut count = 0;
+        for event in self.0.iter().filter(|x| filter.matches(x)) {
+            serde_json::to_writer(&mut *w, event)
+                .map_err(|err| ModelsError::SerializeError(err.to_string()))?;
+            w.write_all(b"\n")
+                .map_err(|err| ModelsError::BufferError(err.to_string()))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Remove all the events generated at or after the given slot
+    pub fn invalidate_from_slot(&mut self, slot: Slot) {
+        self.0.retain(|x| x.context.slot < slot);
+    }
+}
+
+/// Tree of nested calls, each frame holding the events emitted at that depth of the call stack
+#[derive(Default, Debug, Clone)]
+pub struct CallTree {
+    /// events emitted at this frame
+    pub events: Vec<SCOutputEvent>,
+    /// frames called from this one, in order of first appearance
+    pub children: Vec<(Address, CallTree)>,
+}
+
+impl CallTree {
+    /// Insert an event at the frame given by its call stack.
+    /// Events with an empty call stack are attached at the root.
+    pub fn insert(&mut self, event: SCOutputEvent) {
+        let mut frame = self;
+        for addr in event.context.call_stack.iter() {
+            let index = match frame.children.iter().position(|(a, _)| a == addr) {
+                Some(index) => index,
+                None => {
+                    frame.children.push((*addr, CallTree::default()));
+                    frame.children.len() - 1
+                }
+            };
+            frame = &mut frame.children[index].1;
+        }
+        frame.events.push(event);
+    }
+
+    /// Get the frame called from this one by the given address, if any
+    pub fn child(&self, addr: &Address) -> Option<&CallTree> {
+        self.children
+            .iter()
+            .find(|(a, _)| a == addr)
+            .map(|(_, tree)| tree)
+    }
+}
+
+/// Storage backend for smart contract events.
+///
+/// Filtering is done through `EventFilter::matches` so that every backend
+/// answers queries the same way.
+pub trait EventBackend {
+    /// Insert a new event
+    fn insert(&mut self, event: SCOutputEvent);
+
+    /// Get the events matching the given filter
+    fn get_filtered(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent>;
+
+    /// Prune the oldest events if there are more than `max_events`
+    fn prune(&mut self, max_events: usize);
+
+    /// Remove all the events generated at or after the given slot
+    fn invalidate_from_slot(&mut self, slot: Slot);
+}
+
+impl EventBackend for EventStore {
+    fn insert(&mut self, event: SCOutputEvent) {
+        self.push(event);
+    }
+
+    fn get_filtered(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
+        self.get_filtered_sc_output_events(filter)
+    }
+
+    fn prune(&mut self, max_events: usize) {
+        EventStore::prune(self, max_events);
+    }
+
+    fn invalidate_from_slot(&mut self, slot: Slot) {
+        EventStore::invalidate_from_slot(self, slot);
+    }
+}
+
+/// Event store shared between threads, for example between the API and the execution
+#[derive(Default, Debug, Clone)]
+pub struct SharedEventStore(Arc<RwLock<EventStore>>);
+
+impl SharedEventStore {
+    /// Create a new `SharedEventStore` from an existing store
+    pub fn new(store: EventStore) -> Self {
+        SharedEventStore(Arc::new(RwLock::new(store)))
+    }
+
+    /// Lock the store for reading
+    pub fn read(&self) -> RwLockReadGuard<'_, EventStore> {
+        self.0.read()
+    }
+
+    /// Lock the store for writing
+    pub fn write(&self) -> RwLockWriteGuard<'_, EventStore> {
+        self.0.write()
+    }
+
+    /// Get the events matching the filter, waiting for the store to be readable
+    pub fn query(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
+        self.0.read().get_filtered_sc_output_events(filter)
+    }
+
+    /// Get the events matching the filter, or `None` if the store is currently locked for writing
+    pub fn try_query(&self, filter: &EventFilter) -> Option<VecDeque<SCOutputEvent>> {
+        self.0
+            .try_read()
+            .map(|store| store.get_filtered_sc_output_events(filter))
+    }
+}
+
+/// Query running the same filter over several event stores,
+/// for example when events are sharded across stores.
+pub struct FederatedEventQuery<'a> {
+    stores: &'a [&'a EventStore],
+}
+
+impl<'a> FederatedEventQuery<'a> {
+    /// Create a new `FederatedEventQuery` over the given stores
+    pub fn new(stores: &'a [&'a EventStore]) -> Self {
+        FederatedEventQuery { stores }
+    }
+
+    /// Get the events matching the filter in all the stores,
+    /// ordered by slot then index in slot.
+    ///
+    /// Events are identified by their slot and index in slot:
+    /// if several stores contain the same event, it is returned once,
+    /// preferring a final version of the event over a candidate one.
+    /// Remaining ties are broken on the error flag, call stack and data of the events,
+    /// so that the returned version does not depend on the order of the stores.
+    pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> Vec<SCOutputEvent> {
+        let mut events: Vec<SCOutputEvent> = self
+            .stores
+            .iter()
+            .flat_map(|store| store.get_filtered_sc_output_events(filter))
+            .collect();
+        events.sort_by(|a, b| {
+            (a.context.slot, a.context.index_in_slot, !a.context.is_final)
+                .cmp(&(b.context.slot, b.context.index_in_slot, !b.context.is_final))
+                .then_with(|| a.context.is_error.cmp(&b.context.is_error))
+                .then_with(|| a.context.call_stack.cmp(&b.context.call_stack))
+                .then_with(|| a.data.cmp(&b.data))
+        });
+        events.dedup_by_key(|x| (x.context.slot, x.context.index_in_slot));
+        events
+    }
+}
+
+/// `EventStore` with an LRU cache of query results, keyed by filter.
+///
+/// The store is only modified through this wrapper, which drops the cached results
+/// that a modification can change: those of the filters matching an added or removed event.
+/// Appending events past the end slot of a filter, or pruning events before its start slot,
+/// keeps its cached result.
+pub struct CachedEventStore {
+    store: EventStore,
+    cache: Mutex<LruMap<EventFilter, VecDeque<SCOutputEvent>>>,
+}
+
+impl CachedEventStore {
+    /// Create a new `CachedEventStore` holding at most `cache_size` query results
+    pub fn new(store: EventStore, cache_size: u32) -> Self {
+        CachedEventStore {
+            store,
+            cache: Mutex::new(LruMap::new(ByLength::new(cache_size))),
+        }
+    }
+
+    /// Get the underlying event store
+    pub fn store(&self) -> &EventStore {
+        &self.store
+    }
+
+    /// Drop the cached results of the filters matching any of the given events
+    fn invalidate<'a>(
+        cache: &mut LruMap<EventFilter, VecDeque<SCOutputEvent>>,
+        events: impl IntoIterator<Item = &'a SCOutputEvent>,
+    ) {
+        let mut stale = Vec::new();
+        for event in events {
+            stale.extend(
+                cache
+                    .iter()
+                    .filter(|(filter, _)| filter.matches(event))
+                    .map(|(filter, _)| filter.clone()),
+            );
+        }
+        for filter in stale {
+            cache.remove(&filter);
+        }
+    }
+
+    /// Same as `EventStore::extend`
+    pub fn extend(&mut self, other: EventStore) {
+        Self::invalidate(self.cache.get_mut(), &other.0);
+        self.store.extend(other);
+    }
+
+    /// Same as `EventStore::prune`
+    pub fn prune(&mut self, max_events: usize) {
+        let pruned = self.store.0.len().saturating_sub(max_events);
+        Self::invalidate(self.cache.get_mut(), self.store.0.iter().take(pruned));
+        self.store.prune(max_events);
+    }
+
+    /// Get the cached result of a query, if any
+    pub fn get(&self, filter: &EventFilter) -> Option<VecDeque<SCOutputEvent>> {
+        self.cache.lock().get(filter).cloned()
+    }
+
+    /// Same as `EventStore::get_filtered_sc_output_events` but returns the cached result if any
+    pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
+        if let Some(events) = self.get(filter) {
+            return events;
+        }
+        let events = self.store.get_filtered_sc_output_events(filter);
+        self.cache.lock().insert(filter.clone(), events.clone());
+        events
+    }
+
+    /// Remove all the cached results
+    pub fn clear_cache(&mut self) {
+        self.cache.get_mut().clear();
+    }
+}
+
+#[cfg(test)]
+fn make_test_event(slot: Slot, index_in_slot: u64) -> SCOutputEvent {
+    use massa_models::output_event::EventExecutionContext;
+
+    SCOutputEvent {
+        context: EventExecutionContext {
+            slot,
+            block: None,
+            read_only: false,
+            index_in_slot,
+            call_stack: VecDeque::new(),
+            origin_operation_id: None,
+            is_final: false,
+            is_error: false,
+        },
+        data: String::new(),
+    }
+}
+
+#[cfg(test)]
+fn make_test_sc_address(seed: &str) -> Address {
+    use massa_hash::Hash;
+    use massa_models::address::{SCAddress, SCAddressV0};
+
+    Address::SC(SCAddress::SCAddressV0(SCAddressV0(Hash::compute_from(
+        seed.as_bytes(),
+    ))))
+}
+
+#[cfg(test)]
+fn make_test_user_address(seed: &str) -> Address {
+    use massa_hash::Hash;
+    use massa_models::address::{UserAddress, UserAddressV0};
+
+    Address::User(UserAddress::UserAddressV0(UserAddressV0(
+        Hash::compute_from(seed.as_bytes()),
+    )))
 }
 
 #[test]
 fn test_prune() {
     use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
-    use massa_models::slot::Slot;
 
     let mut store = EventStore(VecDeque::new());
     for i in 0..10 {
@@ -127,3 +515,529 @@ fn test_prune() {
     assert_eq!(store.0[1].data, "8");
     assert_eq!(store.0[0].data, "7");
 }
+
+#[test]
+fn test_events_per_slot() {
+    let mut store = EventStore::default();
+    for (period, count) in [(1u64, 3u64), (2, 1), (5, 2)] {
+        for index in 0..count {
+            store.push(make_test_event(Slot::new(period, 0), index));
+        }
+    }
+    let counts = store.events_per_slot();
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![
+            (Slot::new(1, 0), 3),
+            (Slot::new(2, 0), 1),
+            (Slot::new(5, 0), 2)
+        ]
+    );
+}
+
+#[test]
+fn test_event_backend_trait_object() {
+    let mut store = EventStore::default();
+    let backend: &mut dyn EventBackend = &mut store;
+    for period in 0..10 {
+        backend.insert(make_test_event(Slot::new(period, 0), 0));
+    }
+    let filter = EventFilter {
+        start: Some(Slot::new(2, 0)),
+        end: Some(Slot::new(8, 0)),
+        ..Default::default()
+    };
+    assert_eq!(backend.get_filtered(&filter).len(), 6);
+
+    backend.invalidate_from_slot(Slot::new(5, 0));
+    assert_eq!(backend.get_filtered(&filter).len(), 3);
+
+    backend.prune(2);
+    let remaining = backend.get_filtered(&EventFilter::default());
+    assert_eq!(remaining.len(), 2);
+    assert_eq!(remaining[0].context.slot, Slot::new(3, 0));
+    assert_eq!(remaining[1].context.slot, Slot::new(4, 0));
+}
+
+#[test]
+fn test_emitters_at_slot() {
+    let sc_a = make_test_sc_address("a");
+    let sc_b = make_test_sc_address("b");
+    let sc_c = make_test_sc_address("c");
+    let slot = Slot::new(3, 1);
+
+    let mut store = EventStore::default();
+    for (index, emitter) in [sc_a, sc_b, sc_a].into_iter().enumerate() {
+        let mut event = make_test_event(slot, index as u64);
+        event.context.call_stack = vec![sc_c, emitter].into();
+        store.push(event);
+    }
+    let mut other_slot_event = make_test_event(Slot::new(4, 0), 0);
+    other_slot_event.context.call_stack = vec![sc_c].into();
+    store.push(other_slot_event);
+
+    let emitters = store.emitters_at_slot(slot);
+    assert_eq!(emitters.len(), 2);
+    assert!(emitters.contains(&sc_a));
+    assert!(emitters.contains(&sc_b));
+    assert!(store.emitters_at_slot(Slot::new(5, 0)).is_empty());
+}
+
+#[test]
+fn test_cached_event_store() {
+    let mut store = EventStore::default();
+    for period in 0..5 {
+        store.push(make_test_event(Slot::new(period, 0), 0));
+    }
+    let filter = EventFilter {
+        start: Some(Slot::new(1, 0)),
+        ..Default::default()
+    };
+    let mut cached = CachedEventStore::new(store, 10);
+    assert!(cached.get(&filter).is_none());
+    assert_eq!(cached.get_filtered_sc_output_events(&filter).len(), 4);
+    assert_eq!(cached.get(&filter).unwrap().len(), 4);
+
+    let mut new_events = EventStore::default();
+    new_events.push(make_test_event(Slot::new(5, 0), 0));
+    cached.extend(new_events);
+    assert!(cached.get(&filter).is_none());
+    assert_eq!(cached.get_filtered_sc_output_events(&filter).len(), 5);
+
+    cached.prune(2);
+    assert!(cached.get(&filter).is_none());
+    assert_eq!(cached.get_filtered_sc_output_events(&filter).len(), 2);
+}
+
+#[test]
+fn test_cached_event_store_partial_invalidation() {
+    let mut store = EventStore::default();
+    for period in 0..4 {
+        store.push(make_test_event(Slot::new(period, 0), 0));
+    }
+    let mut cached = CachedEventStore::new(store, 10);
+    let early = EventFilter {
+        end: Some(Slot::new(2, 0)),
+        ..Default::default()
+    };
+    let late = EventFilter {
+        start: Some(Slot::new(2, 0)),
+        ..Default::default()
+    };
+    cached.get_filtered_sc_output_events(&early);
+    cached.get_filtered_sc_output_events(&late);
+
+    // appending past the end slot of a filter keeps its cached result
+    let mut new_events = EventStore::default();
+    new_events.push(make_test_event(Slot::new(4, 0), 0));
+    cached.extend(new_events);
+    assert_eq!(cached.get(&early).unwrap().len(), 2);
+    assert!(cached.get(&late).is_none());
+    assert_eq!(cached.get_filtered_sc_output_events(&late).len(), 3);
+
+    // pruning before the start slot of a filter keeps its cached result
+    cached.prune(4);
+    assert!(cached.get(&early).is_none());
+    assert_eq!(cached.get(&late).unwrap().len(), 3);
+
+    // cached results always agree with the store
+    for filter in [&early, &late] {
+        assert_eq!(
+            cached.get_filtered_sc_output_events(filter).len(),
+            cached.store().get_filtered_sc_output_events(filter).len()
+        );
+    }
+}
+
+#[test]
+fn test_drain_filtered() {
+    let mut store = EventStore::default();
+    for period in 0..6 {
+        let mut event = make_test_event(Slot::new(period, 0), 0);
+        event.context.is_error = period % 2 == 0;
+        store.push(event);
+    }
+    let filter = EventFilter {
+        is_error: Some(true),
+        ..Default::default()
+    };
+
+    let drained = store.drain_filtered(&filter);
+    assert_eq!(
+        drained.iter().map(|x| x.context.slot).collect::<Vec<_>>(),
+        vec![Slot::new(0, 0), Slot::new(2, 0), Slot::new(4, 0)]
+    );
+    assert!(store.get_filtered_sc_output_events(&filter).is_empty());
+    assert_eq!(
+        store.0.iter().map(|x| x.context.slot).collect::<Vec<_>>(),
+        vec![Slot::new(1, 0), Slot::new(3, 0), Slot::new(5, 0)]
+    );
+}
+
+#[test]
+fn test_filter_moved_to_thread() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<EventFilter>();
+
+    let mut store = EventStore::default();
+    for period in 0..4 {
+        store.push(make_test_event(Slot::new(period, 0), 0));
+    }
+    let filter = EventFilter {
+        end: Some(Slot::new(2, 0)),
+        ..Default::default()
+    };
+    let handle = std::thread::spawn(move || store.get_filtered_sc_output_events(&filter).len());
+    assert_eq!(handle.join().unwrap(), 2);
+}
+
+#[test]
+fn test_federated_event_query() {
+    let mut store_a = EventStore::default();
+    let mut store_b = EventStore::default();
+    for (period, index_in_slot) in [(1, 0), (1, 2), (3, 0)] {
+        store_a.push(make_test_event(Slot::new(period, 0), index_in_slot));
+    }
+    // (1, 2) is present in both stores, and only final in the second one
+    for (period, index_in_slot) in [(1, 1), (1, 2), (2, 0)] {
+        let mut event = make_test_event(Slot::new(period, 0), index_in_slot);
+        event.context.is_final = true;
+        store_b.push(event);
+    }
+    // (3, 0) is a candidate in both stores, with different data
+    let mut event = make_test_event(Slot::new(3, 0), 0);
+    event.data = "other data".to_string();
+    store_b.push(event);
+
+    let expected = vec![
+        (Slot::new(1, 0), 0),
+        (Slot::new(1, 0), 1),
+        (Slot::new(1, 0), 2),
+        (Slot::new(2, 0), 0),
+        (Slot::new(3, 0), 0),
+    ];
+    for stores in [[&store_a, &store_b], [&store_b, &store_a]] {
+        let events =
+            FederatedEventQuery::new(&stores).get_filtered_sc_output_events(&Default::default());
+        assert_eq!(
+            events
+                .iter()
+                .map(|x| (x.context.slot, x.context.index_in_slot))
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert!(events[2].context.is_final);
+        assert_eq!(events[4].data, "");
+    }
+}
+
+#[test]
+fn test_reserve() {
+    let mut store = EventStore::default();
+    store.reserve(100);
+    assert!(store.0.capacity() >= 100);
+    for period in 0..10 {
+        store.push(make_test_event(Slot::new(period, 0), 0));
+    }
+    let events = store.get_filtered_sc_output_events(&EventFilter {
+        start: Some(Slot::new(5, 0)),
+        ..Default::default()
+    });
+    assert_eq!(
+        events.iter().map(|x| x.context.slot).collect::<Vec<_>>(),
+        (5..10)
+            .map(|period| Slot::new(period, 0))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_sample() {
+    let mut store = EventStore::default();
+    for period in 0..20 {
+        let mut event = make_test_event(Slot::new(period, 0), 0);
+        event.context.is_error = period % 2 == 1;
+        store.push(event);
+    }
+    let filter = EventFilter {
+        is_error: Some(false),
+        ..Default::default()
+    };
+
+    assert_eq!(store.sample(&filter, 1).len(), 10);
+    let sample = store.sample(&filter, 3);
+    assert_eq!(
+        sample.iter().map(|x| x.context.slot).collect::<Vec<_>>(),
+        vec![
+            Slot::new(0, 0),
+            Slot::new(6, 0),
+            Slot::new(12, 0),
+            Slot::new(18, 0)
+        ]
+    );
+    let other_sample = store.sample(&filter, 3);
+    assert_eq!(
+        sample.iter().map(|x| x.context.slot).collect::<Vec<_>>(),
+        other_sample
+            .iter()
+            .map(|x| x.context.slot)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_pressure() {
+    let max_events = 4;
+    let mut store = EventStore::default();
+    assert_eq!(store.pressure(max_events), 0.0);
+    let mut previous_pressure = 0.0;
+    for period in 0..4 {
+        store.push(make_test_event(Slot::new(period, 0), 0));
+        let pressure = store.pressure(max_events);
+        assert!(pressure > previous_pressure);
+        previous_pressure = pressure;
+    }
+    assert_eq!(store.pressure(max_events), 1.0);
+    assert!(store.is_overloaded(max_events, 0.9));
+    store.prune(2);
+    assert_eq!(store.pressure(max_events), 0.5);
+    assert!(!store.is_overloaded(max_events, 0.9));
+}
+
+#[test]
+fn test_call_tree_at_slot() {
+    let user = make_test_user_address("user");
+    let sc_a = make_test_sc_address("a");
+    let sc_b = make_test_sc_address("b");
+    let sc_c = make_test_sc_address("c");
+    let slot = Slot::new(2, 0);
+
+    let mut store = EventStore::default();
+    for (index, call_stack) in [
+        vec![],
+        vec![user, sc_a],
+        vec![user, sc_a, sc_b],
+        vec![user, sc_a, sc_b],
+        vec![user, sc_c],
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut event = make_test_event(slot, index as u64);
+        event.context.call_stack = call_stack.into();
+        store.push(event);
+    }
+    let mut other_slot_event = make_test_event(Slot::new(3, 0), 0);
+    other_slot_event.context.call_stack = vec![user].into();
+    store.push(other_slot_event);
+
+    let tree = store.call_tree_at_slot(slot);
+    assert_eq!(tree.events.len(), 1);
+    assert_eq!(tree.children.len(), 1);
+    let user_frame = tree.child(&user).unwrap();
+    assert!(user_frame.events.is_empty());
+    assert_eq!(
+        user_frame
+            .children
+            .iter()
+            .map(|(addr, _)| *addr)
+            .collect::<Vec<_>>(),
+        vec![sc_a, sc_c]
+    );
+    let sc_a_frame = user_frame.child(&sc_a).unwrap();
+    assert_eq!(sc_a_frame.events.len(), 1);
+    let sc_b_frame = sc_a_frame.child(&sc_b).unwrap();
+    assert_eq!(
+        sc_b_frame
+            .events
+            .iter()
+            .map(|x| x.context.index_in_slot)
+            .collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert!(sc_b_frame.children.is_empty());
+    assert_eq!(user_frame.child(&sc_c).unwrap().events.len(), 1);
+}
+
+#[test]
+fn test_latest_per_emitter() {
+    let sc_a = make_test_sc_address("a");
+    let sc_b = make_test_sc_address("b");
+
+    let mut store = EventStore::default();
+    for (period, index_in_slot, emitter) in [
+        (2, 0, sc_a),
+        (2, 3, sc_a),
+        (1, 5, sc_a),
+        (1, 0, sc_b),
+        (4, 1, sc_b),
+        (4, 0, sc_b),
+    ] {
+        let mut event = make_test_event(Slot::new(period, 0), index_in_slot);
+        event.context.call_stack = vec![emitter].into();
+        store.push(event);
+    }
+    store.push(make_test_event(Slot::new(9, 0), 0));
+
+    let latest = store.latest_per_emitter();
+    assert_eq!(latest.len(), 2);
+    assert_eq!(latest[&sc_a].context.slot, Slot::new(2, 0));
+    assert_eq!(latest[&sc_a].context.index_in_slot, 3);
+    assert_eq!(latest[&sc_b].context.slot, Slot::new(4, 0));
+    assert_eq!(latest[&sc_b].context.index_in_slot, 1);
+}
+
+#[test]
+fn test_upsert() {
+    let mut store = EventStore::default();
+    assert!(!store.upsert(make_test_event(Slot::new(1, 0), 0)));
+    assert!(!store.upsert(make_test_event(Slot::new(1, 0), 1)));
+
+    let mut event = make_test_event(Slot::new(1, 0), 0);
+    event.data = "first retry".to_string();
+    assert!(store.upsert(event.clone()));
+    event.data = "second retry".to_string();
+    assert!(store.upsert(event));
+
+    assert_eq!(store.0.len(), 2);
+    assert_eq!(store.0[0].context.index_in_slot, 0);
+    assert_eq!(store.0[0].data, "second retry");
+    assert_eq!(store.0[1].context.index_in_slot, 1);
+}
+
+#[test]
+fn test_shared_event_store() {
+    let shared = SharedEventStore::default();
+    let writer = std::thread::spawn({
+        let shared = shared.clone();
+        move || {
+            for period in 0..100 {
+                shared
+                    .write()
+                    .push(make_test_event(Slot::new(period, 0), 0));
+            }
+        }
+    });
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                let mut previous_len = 0;
+                for _ in 0..100 {
+                    let len = shared.query(&EventFilter::default()).len();
+                    assert!(len >= previous_len);
+                    previous_len = len;
+                }
+            })
+        })
+        .collect();
+    writer.join().unwrap();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    assert_eq!(shared.query(&EventFilter::default()).len(), 100);
+    {
+        let _guard = shared.write();
+        assert!(shared.try_query(&EventFilter::default()).is_none());
+    }
+    assert_eq!(
+        shared.try_query(&EventFilter::default()).unwrap().len(),
+        100
+    );
+}
+
+#[test]
+fn test_filtered_events_in_final_blocks() {
+    use massa_hash::Hash;
+    use massa_models::secure_share::Id;
+
+    let final_block = BlockId::new(Hash::compute_from("final".as_bytes()));
+    let candidate_block = BlockId::new(Hash::compute_from("candidate".as_bytes()));
+    let finalized_blocks: PreHashSet<BlockId> = [final_block].into_iter().collect();
+
+    let mut store = EventStore::default();
+    for (index, block) in [
+        Some(final_block),
+        Some(candidate_block),
+        None,
+        Some(final_block),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut event = make_test_event(Slot::new(1, 0), index as u64);
+        event.context.block = block;
+        store.push(event);
+    }
+
+    let events = store
+        .get_filtered_sc_output_events_in_final_blocks(&EventFilter::default(), |id| {
+            finalized_blocks.contains(id)
+        });
+    assert_eq!(
+        events
+            .iter()
+            .map(|x| x.context.index_in_slot)
+            .collect::<Vec<_>>(),
+        vec![0, 3]
+    );
+}
+
+#[test]
+fn test_events_for_operation_ordered() {
+    use massa_hash::Hash;
+    use massa_models::secure_share::Id;
+
+    let op = OperationId::new(Hash::compute_from("op".as_bytes()));
+    let other_op = OperationId::new(Hash::compute_from("other op".as_bytes()));
+
+    let mut store = EventStore::default();
+    for (period, index_in_slot, op) in [
+        (2, 1, op),
+        (1, 4, op),
+        (2, 0, op),
+        (1, 2, other_op),
+        (1, 3, op),
+    ] {
+        let mut event = make_test_event(Slot::new(period, 0), index_in_slot);
+        event.context.origin_operation_id = Some(op);
+        store.push(event);
+    }
+    store.push(make_test_event(Slot::new(1, 0), 0));
+
+    let events = store.events_for_operation_ordered(&op);
+    assert_eq!(
+        events
+            .iter()
+            .map(|x| (x.context.slot.period, x.context.index_in_slot))
+            .collect::<Vec<_>>(),
+        vec![(1, 3), (1, 4), (2, 0), (2, 1)]
+    );
+}
+
+#[test]
+fn test_write_ndjson() {
+    let mut store = EventStore::default();
+    for period in 0..6 {
+        let mut event = make_test_event(Slot::new(period, 0), 0);
+        event.data = format!("{{\"line\":\n{}}}", period);
+        store.push(event);
+    }
+    let filter = EventFilter {
+        start: Some(Slot::new(2, 0)),
+        ..Default::default()
+    };
+
+    let mut buffer = Vec::new();
+    let count = store.write_ndjson(&filter, &mut buffer).unwrap();
+    assert_eq!(count, 4);
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), count);
+    for (line, period) in lines.into_iter().zip(2..) {
+        let event: SCOutputEvent = serde_json::from_str(line).unwrap();
+        assert_eq!(event.context.slot, Slot::new(period, 0));
+        assert_eq!(event.data, format!("{{\"line\":\n{}}}", period));
+    }
+}
@@ -58,7 +58,9 @@ pub use channels::ExecutionChannels;
 pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
-pub use event_store::EventStore;
+pub use event_store::{
+    CachedEventStore, CallTree, EventBackend, EventStore, FederatedEventQuery, SharedEventStore,
+};
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
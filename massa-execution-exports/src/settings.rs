@@ -26,6 +26,8 @@ pub struct ExecutionConfig {
     pub readonly_queue_length: usize,
     /// maximum number of SC output events kept in cache
     pub max_final_events: usize,
+    /// maximum number of final SC output event query results kept in cache
+    pub event_query_cache_size: u32,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
     /// maximum gas per block
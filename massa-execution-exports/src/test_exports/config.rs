@@ -22,6 +22,7 @@ impl Default for ExecutionConfig {
         Self {
             readonly_queue_length: 100,
             max_final_events: 1000,
+            event_query_cache_size: 10,
             max_async_gas: MAX_ASYNC_GAS,
             thread_count: THREAD_COUNT,
             roll_price: ROLL_PRICE,
@@ -14,10 +14,10 @@ use crate::interface_impl::InterfaceImpl;
 use crate::stats::ExecutionStatsCounter;
 use massa_async_pool::AsyncMessage;
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    CachedEventStore, EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels,
+    ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos,
+    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 use massa_final_state::FinalState;
 use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
@@ -70,8 +70,8 @@ pub(crate) struct ExecutionState {
     pub active_cursor: Slot,
     // a cursor pointing to the highest executed final slot
     pub final_cursor: Slot,
-    // store containing execution events that became final
-    final_events: EventStore,
+    // store containing execution events that became final, caching the results of repeated queries
+    final_events: CachedEventStore,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
@@ -160,7 +160,10 @@ impl ExecutionState {
             // empty execution output history: it is not recovered through bootstrap
             active_history,
             // empty final event store: it is not recovered through bootstrap
-            final_events: Default::default(),
+            final_events: CachedEventStore::new(
+                EventStore::default(),
+                config.event_query_cache_size,
+            ),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
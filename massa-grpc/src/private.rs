@@ -261,7 +261,7 @@ pub(crate) fn get_node_status(
     connected_nodes.sort_by(|a, b| a.node_ip.cmp(&b.node_ip));
 
     let current_cycle = last_slot
-        .unwrap_or_else(|| Slot::new(0, 0))
+        .unwrap_or_else(Slot::genesis)
         .get_cycle(grpc.grpc_config.periods_per_cycle);
     let cycle_duration = grpc
         .grpc_config
@@ -397,7 +397,7 @@ pub(crate) fn get_stakers(
             cur_slot.get_cycle(grpc.grpc_config.periods_per_cycle),
             cur_slot,
         ),
-        Ok(None) => (0, Slot::new(0, 0)),
+        Ok(None) => (0, Slot::genesis()),
         Err(e) => return Err(GrpcError::ModelsError(e)),
     };
 
@@ -524,7 +524,16 @@ pub(crate) fn get_sc_execution_events(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::GetScExecutionEventsRequest>,
 ) -> Result<grpc_api::GetScExecutionEventsResponse, GrpcError> {
-    let event_filter = to_event_filter(request.into_inner().filters)?;
+    // an empty filter would scan every stored event: the caller has to ask for it explicitly,
+    // for example with a slot range starting at genesis
+    let event_filter = to_event_filter(request.into_inner().filters)?
+        .require_some()
+        .map_err(|_| {
+            GrpcError::InvalidArgument(
+                "at least one filter is required, use a slot range to query all the events"
+                    .to_string(),
+            )
+        })?;
     let events: Vec<grpc_model::ScExecutionEvent> = grpc
         .execution_controller
         .get_filtered_sc_output_event(event_filter)
@@ -599,10 +608,10 @@ pub(crate) fn get_selector_draws(
             ));
         }
 
-        let mut start_slot = Slot::new(0, 0); // inclusive
+        let mut start_slot = Slot::genesis(); // inclusive
         let mut end_slot = Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1); // exclusive
         for slot_range in &slot_ranges {
-            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
+            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(Slot::genesis));
             end_slot = end_slot.min(
                 slot_range
                     .end_slot
@@ -675,7 +684,7 @@ pub(crate) fn get_status(
     )?;
 
     let current_cycle = last_slot
-        .unwrap_or_else(|| Slot::new(0, 0))
+        .unwrap_or_else(Slot::genesis)
         .get_cycle(grpc.grpc_config.periods_per_cycle);
     let cycle_duration = grpc
         .grpc_config
@@ -872,10 +881,10 @@ pub(crate) fn search_blocks(
 
     // filter by slot ranges
     if let Some(slot_ranges) = slot_ranges_filter {
-        let mut start_slot = Slot::new(0, 0); // inclusive
+        let mut start_slot = Slot::genesis(); // inclusive
         let mut end_slot = Slot::new(u64::MAX, grpc.grpc_config.thread_count - 1); // exclusive
         for slot_range in &slot_ranges {
-            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
+            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(Slot::genesis));
             end_slot = end_slot.min(
                 slot_range
                     .end_slot
@@ -362,11 +362,11 @@ fn filter_map_exec_output(
     grpc_config: &GrpcConfig,
 ) -> Option<ExecutionOutput> {
     if let Some(slot_ranges) = &filters.slot_ranges_filter {
-        let mut start_slot = Slot::new(0, 0); // inclusive
+        let mut start_slot = Slot::genesis(); // inclusive
         let mut end_slot = Slot::new(u64::MAX, grpc_config.thread_count - 1); // exclusive
 
         for slot_range in slot_ranges {
-            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
+            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(Slot::genesis));
             end_slot = end_slot.min(
                 slot_range
                     .end_slot
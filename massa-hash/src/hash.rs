@@ -452,4 +452,20 @@ mod tests {
         ];
         assert_eq!(hash.into_bytes(), hash_ref);
     }
+
+    #[test]
+    #[serial]
+    fn test_bs58_check_leading_zeros() {
+        let mut bytes = [0u8; HASH_SIZE_BYTES];
+        bytes[3..].copy_from_slice(&[0xffu8; HASH_SIZE_BYTES - 3]);
+        let hash = Hash::from_bytes(&bytes);
+        let serialized = hash.to_bs58_check();
+        // bs58 encodes each leading zero byte as a leading '1'
+        assert!(serialized.starts_with("111"));
+        let deserialized = Hash::from_bs58_check(&serialized).unwrap();
+        assert_eq!(deserialized.into_bytes(), bytes);
+
+        let zero = Hash::from_bytes(&[0u8; HASH_SIZE_BYTES]);
+        assert_eq!(Hash::from_bs58_check(&zero.to_bs58_check()).unwrap(), zero);
+    }
 }
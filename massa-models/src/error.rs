@@ -62,6 +62,8 @@ pub enum ModelsError {
     OutdatedBootstrapCursor,
     /// Error raised {0}
     ErrorRaised(String),
+    /// event filter without any constraint
+    EmptyEventFilterError,
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for ModelsError {
@@ -1,18 +1,25 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{address::Address, operation::OperationId, slot::Slot};
+use crate::{
+    address::Address, error::ModelsError, operation::OperationId, output_event::SCOutputEvent,
+    slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 
 /// filter used when retrieving SC output events
-#[derive(Default, Debug, Deserialize, Clone, Serialize)]
+///
+/// Note that `emitter_address` is compared with the first entry of the call stack
+/// (`SCOutputEvent::origin`) and `original_caller_address` with the last one
+/// (`SCOutputEvent::emitter`), the opposite of what their names suggest.
+#[derive(Default, Debug, Deserialize, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct EventFilter {
     /// optional start slot
     pub start: Option<Slot>,
     /// optional end slot
     pub end: Option<Slot>,
-    /// optional emitter address
+    /// optional emitter address, see `EventFilter` for the call stack entry it is compared with
     pub emitter_address: Option<Address>,
-    /// optional caller address
+    /// optional caller address, see `EventFilter` for the call stack entry it is compared with
     pub original_caller_address: Option<Address>,
     /// optional operation id
     pub original_operation_id: Option<OperationId>,
@@ -28,4 +35,396 @@ pub struct EventFilter {
     /// Some(false) means events coming from a succeeded sc execution
     /// None means both
     pub is_error: Option<bool>,
+    /// optional address at the origin of the call stack
+    ///
+    /// Only events emitted by a smart contract in a call stack
+    /// originated by this address match
+    pub triggered_by: Option<Address>,
+    /// optional minimum call stack depth
+    ///
+    /// Some(2) only matches events emitted from nested calls
+    pub min_call_depth: Option<usize>,
+    /// explicitly match every event when no other constraint is set
+    ///
+    /// This does not change which events match,
+    /// it only makes `EventFilter::require_some` accept a filter without constraint
+    #[serde(default)]
+    pub match_all: bool,
+}
+
+/// Outcome of each `EventFilter` predicate for a given event.
+///
+/// `None` means that the predicate is not set in the filter,
+/// `Some(false)` means that it excluded the event.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct FilterMatch {
+    /// start slot predicate
+    pub start: Option<bool>,
+    /// end slot predicate
+    pub end: Option<bool>,
+    /// emitter address predicate
+    pub emitter_address: Option<bool>,
+    /// caller address predicate
+    pub original_caller_address: Option<bool>,
+    /// operation id predicate
+    pub original_operation_id: Option<bool>,
+    /// event status predicate
+    pub is_final: Option<bool>,
+    /// execution status predicate
+    pub is_error: Option<bool>,
+    /// call stack origin predicate
+    pub triggered_by: Option<bool>,
+    /// minimum call stack depth predicate
+    pub min_call_depth: Option<bool>,
+}
+
+impl FilterMatch {
+    /// Check whether no predicate excluded the event
+    pub fn is_match(&self) -> bool {
+        [
+            self.start,
+            self.end,
+            self.emitter_address,
+            self.original_caller_address,
+            self.original_operation_id,
+            self.is_final,
+            self.is_error,
+            self.triggered_by,
+            self.min_call_depth,
+        ]
+        .iter()
+        .all(|x| *x != Some(false))
+    }
+}
+
+impl EventFilter {
+    /// Filter explicitly matching every event
+    pub fn match_all() -> Self {
+        EventFilter {
+            match_all: true,
+            ..Default::default()
+        }
+    }
+
+    /// Check whether the filter has no constraint at all, whether or not `match_all` is set.
+    /// A minimum call depth of 0 matches every event, so it is not a constraint.
+    pub fn is_empty(&self) -> bool {
+        EventFilter {
+            min_call_depth: self.min_call_depth.filter(|depth| *depth > 0),
+            match_all: false,
+            ..self.clone()
+        } == EventFilter::default()
+    }
+
+    /// Return the filter if it has at least one constraint or explicitly matches all events,
+    /// an error otherwise.
+    /// Use `EventFilter::match_all` to explicitly query every event.
+    pub fn require_some(self) -> Result<Self, ModelsError> {
+        if self.is_empty() && !self.match_all {
+            Err(ModelsError::EmptyEventFilterError)
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Filter matching the events emitted by any smart contract
+    /// in a call stack originated by the given user address
+    pub fn user_triggered_contract_events(user: Address) -> Self {
+        EventFilter {
+            triggered_by: Some(user),
+            ..Default::default()
+        }
+    }
+
+    /// Check whether an event matches all the constraints of the filter
+    ///
+    /// Predicates are evaluated from the cheapest to the most expensive one
+    /// (booleans and call depth, then slots, then addresses and operation ids) so that
+    /// events are rejected as early as possible when scanning large stores.
+    pub fn matches(&self, event: &SCOutputEvent) -> bool {
+        if let Some(is_final) = self.is_final {
+            if event.context.is_final != is_final {
+                return false;
+            }
+        }
+        if let Some(is_error) = self.is_error {
+            if event.context.is_error != is_error {
+                return false;
+            }
+        }
+        if let Some(min_call_depth) = self.min_call_depth {
+            if event.context.call_stack.len() < min_call_depth {
+                return false;
+            }
+        }
+        if let Some(start) = self.start {
+            if event.context.slot < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.end {
+            if event.context.slot >= end {
+                return false;
+            }
+        }
+        match (self.emitter_address, event.context.call_stack.front()) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (
+            self.original_caller_address,
+            event.context.call_stack.back(),
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (
+            self.original_operation_id,
+            event.context.origin_operation_id,
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        if let Some(triggered_by) = self.triggered_by {
+            if event.origin() != Some(&triggered_by)
+                || !matches!(event.emitter(), Some(Address::SC(_)))
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Evaluate every predicate of the filter on an event, to find out which ones exclude it.
+    /// This is a debugging helper: use `EventFilter::matches` to actually filter events.
+    pub fn explain(&self, event: &SCOutputEvent) -> FilterMatch {
+        FilterMatch {
+            start: self.start.map(|start| event.context.slot >= start),
+            end: self.end.map(|end| event.context.slot < end),
+            emitter_address: self
+                .emitter_address
+                .map(|addr| event.context.call_stack.front() == Some(&addr)),
+            original_caller_address: self
+                .original_caller_address
+                .map(|addr| event.context.call_stack.back() == Some(&addr)),
+            original_operation_id: self
+                .original_operation_id
+                .map(|id| event.context.origin_operation_id == Some(id)),
+            is_final: self
+                .is_final
+                .map(|is_final| event.context.is_final == is_final),
+            is_error: self
+                .is_error
+                .map(|is_error| event.context.is_error == is_error),
+            triggered_by: self.triggered_by.map(|addr| {
+                event.origin() == Some(&addr) && matches!(event.emitter(), Some(Address::SC(_)))
+            }),
+            min_call_depth: self
+                .min_call_depth
+                .map(|min_call_depth| event.context.call_stack.len() >= min_call_depth),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{SCAddress, SCAddressV0, UserAddress, UserAddressV0};
+    use crate::output_event::EventExecutionContext;
+    use crate::secure_share::Id;
+    use massa_hash::Hash;
+
+    fn sc_address(seed: &str) -> Address {
+        Address::SC(SCAddress::SCAddressV0(SCAddressV0(Hash::compute_from(
+            seed.as_bytes(),
+        ))))
+    }
+
+    fn user_address(seed: &str) -> Address {
+        Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            Hash::compute_from(seed.as_bytes()),
+        )))
+    }
+
+    fn event(slot: Slot, call_stack: Vec<Address>) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: call_stack.into(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_matches_agrees_with_explain() {
+        // The evaluation order of `matches` only matters for performance: every predicate
+        // is a side-effect free comparison, so the order can not be observed from the result.
+        // What has to hold is that the early returns give the same outcome as evaluating
+        // every predicate, which is what `explain` does.
+        let user = user_address("user");
+        let sc = sc_address("sc");
+        let operation_id = OperationId::new(Hash::compute_from("op".as_bytes()));
+
+        let mut events = Vec::new();
+        for call_stack in [vec![], vec![user], vec![user, sc], vec![sc, user]] {
+            for is_final in [false, true] {
+                for is_error in [false, true] {
+                    let mut e = event(Slot::new(2, 0), call_stack.clone());
+                    e.context.is_final = is_final;
+                    e.context.is_error = is_error;
+                    events.push(e.clone());
+                    e.context.origin_operation_id = Some(operation_id);
+                    events.push(e);
+                }
+            }
+        }
+
+        let filters = vec![
+            EventFilter::match_all(),
+            EventFilter {
+                start: Some(Slot::new(1, 0)),
+                end: Some(Slot::new(2, 0)),
+                ..Default::default()
+            },
+            EventFilter {
+                start: Some(Slot::new(2, 0)),
+                end: Some(Slot::new(3, 0)),
+                is_final: Some(true),
+                ..Default::default()
+            },
+            EventFilter {
+                is_final: Some(false),
+                emitter_address: Some(sc),
+                ..Default::default()
+            },
+            EventFilter {
+                is_error: Some(true),
+                original_caller_address: Some(user),
+                original_operation_id: Some(operation_id),
+                ..Default::default()
+            },
+            EventFilter {
+                min_call_depth: Some(2),
+                triggered_by: Some(user),
+                ..Default::default()
+            },
+            EventFilter::user_triggered_contract_events(sc),
+        ];
+
+        for filter in &filters {
+            for e in &events {
+                assert_eq!(
+                    filter.matches(e),
+                    filter.explain(e).is_match(),
+                    "filter {:?} on event {:?}",
+                    filter,
+                    e
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_triggered_contract_events() {
+        let user = user_address("user");
+        let other_user = user_address("other user");
+        let sc_a = sc_address("a");
+        let sc_b = sc_address("b");
+        let filter = EventFilter::user_triggered_contract_events(user);
+
+        // emitted by a contract called by the user
+        assert!(filter.matches(&event(Slot::new(1, 0), vec![user, sc_a])));
+        // emitted by a contract called by a contract called by the user
+        assert!(filter.matches(&event(Slot::new(1, 0), vec![user, sc_a, sc_b])));
+        // emitted directly by the user, through bytecode execution
+        assert!(!filter.matches(&event(Slot::new(1, 0), vec![user])));
+        // emitted by a contract called by another user
+        assert!(!filter.matches(&event(Slot::new(1, 0), vec![other_user, sc_a])));
+    }
+
+    #[test]
+    fn test_require_some_filter() {
+        assert!(EventFilter::default().require_some().is_err());
+        let filter = EventFilter {
+            is_final: Some(false),
+            ..Default::default()
+        }
+        .require_some()
+        .unwrap();
+        assert!(filter.matches(&event(Slot::new(1, 0), vec![])));
+        assert!(EventFilter::match_all().matches(&event(Slot::new(1, 0), vec![])));
+        assert!(EventFilter::match_all().require_some().is_ok());
+        assert!(EventFilter::match_all().is_empty());
+
+        // API clients opt into matching every event with `match_all`
+        let filter: EventFilter = serde_json::from_str("{}").unwrap();
+        assert!(filter.require_some().is_err());
+        let filter: EventFilter = serde_json::from_str("{\"match_all\": true}").unwrap();
+        assert_eq!(filter, EventFilter::match_all());
+        assert!(filter.require_some().is_ok());
+        let filter: EventFilter = serde_json::from_str("{\"min_call_depth\": 0}").unwrap();
+        assert!(filter.require_some().is_err());
+    }
+
+    #[test]
+    fn test_filter_explain() {
+        let sc = sc_address("sc");
+        let sc_event = event(Slot::new(3, 0), vec![sc]);
+        let filter = EventFilter {
+            start: Some(Slot::new(5, 0)),
+            emitter_address: Some(sc),
+            is_final: Some(false),
+            ..Default::default()
+        };
+
+        let explanation = filter.explain(&sc_event);
+        assert_eq!(
+            explanation,
+            FilterMatch {
+                start: Some(false),
+                emitter_address: Some(true),
+                is_final: Some(true),
+                ..Default::default()
+            }
+        );
+        assert!(!explanation.is_match());
+        assert!(!filter.matches(&sc_event));
+    }
+
+    #[test]
+    fn test_min_call_depth() {
+        let user = user_address("user");
+        let sc_a = sc_address("a");
+        let sc_b = sc_address("b");
+        let direct = event(Slot::new(1, 0), vec![sc_a]);
+        let nested = event(Slot::new(1, 0), vec![user, sc_a]);
+        let deeply_nested = event(Slot::new(1, 0), vec![user, sc_a, sc_b]);
+
+        for min_call_depth in [None, Some(0), Some(1)] {
+            let filter = EventFilter {
+                min_call_depth,
+                ..Default::default()
+            };
+            assert!(filter.matches(&direct));
+            assert!(filter.matches(&nested));
+            assert!(filter.matches(&deeply_nested));
+        }
+        let filter = EventFilter {
+            min_call_depth: Some(2),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&direct));
+        assert!(filter.matches(&nested));
+        assert!(filter.matches(&deeply_nested));
+    }
 }
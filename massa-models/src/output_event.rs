@@ -1,6 +1,10 @@
 use crate::{address::Address, block_id::BlockId, operation::OperationId, slot::Slot};
+use massa_hash::Hash;
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, fmt::Display};
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// By product of a byte code execution
@@ -11,6 +15,104 @@ pub struct SCOutputEvent {
     pub data: String,
 }
 
+impl SCOutputEvent {
+    /// Address of the smart contract that emitted the event (most recent call stack entry).
+    /// See `EventFilter` for the filter field matching it.
+    pub fn emitter(&self) -> Option<&Address> {
+        self.context.call_stack.back()
+    }
+
+    /// Address at the origin of the call stack that led to the event.
+    /// See `EventFilter` for the filter field matching it.
+    pub fn origin(&self) -> Option<&Address> {
+        self.context.call_stack.front()
+    }
+
+    /// Name of the contract method that the event data is prefixed with, if any.
+    ///
+    /// Returns the part of the data before the first `:` when it is a non-empty
+    /// identifier (ASCII alphanumeric characters and `_`), so that `"Transfer:{...}"`
+    /// gives `Some("Transfer")` while plain JSON data gives `None`.
+    pub fn method_hint(&self) -> Option<&str> {
+        let (method, _) = self.data.split_once(':')?;
+        if !method.is_empty()
+            && method
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            Some(method)
+        } else {
+            None
+        }
+    }
+
+    /// List the fields that differ between this event and another one.
+    /// Identical events give an empty list.
+    pub fn diff(&self, other: &Self) -> Vec<EventFieldDiff> {
+        let mut diffs = Vec::new();
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "slot",
+            &self.context.slot,
+            &other.context.slot,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "block",
+            &self.context.block,
+            &other.context.block,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "read_only",
+            &self.context.read_only,
+            &other.context.read_only,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "index_in_slot",
+            &self.context.index_in_slot,
+            &other.context.index_in_slot,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "call_stack",
+            &self.context.call_stack,
+            &other.context.call_stack,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "origin_operation_id",
+            &self.context.origin_operation_id,
+            &other.context.origin_operation_id,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "is_final",
+            &self.context.is_final,
+            &other.context.is_final,
+        );
+        EventFieldDiff::push_if_different(
+            &mut diffs,
+            "is_error",
+            &self.context.is_error,
+            &other.context.is_error,
+        );
+        EventFieldDiff::push_if_different(&mut diffs, "data", &self.data, &other.data);
+        diffs
+    }
+
+    /// Deterministically assign the event to one of `num_shards` shards
+    /// based on its slot and index in slot. Returns 0 if `num_shards` is 0.
+    pub fn shard(&self, num_shards: u32) -> u32 {
+        let mut bytes = self.context.slot.to_bytes_key().to_vec();
+        bytes.extend(self.context.index_in_slot.to_be_bytes());
+        let hash = Hash::compute_from(&bytes).into_bytes();
+        let value = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+        value.checked_rem(num_shards).unwrap_or(0)
+    }
+}
+
 impl Display for SCOutputEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Context: {}", self.context)?;
@@ -18,6 +120,64 @@ impl Display for SCOutputEvent {
     }
 }
 
+/// Difference on a single field between two events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFieldDiff {
+    /// name of the field
+    pub field: &'static str,
+    /// debug representation of the field in the first event
+    pub old: String,
+    /// debug representation of the field in the other event
+    pub new: String,
+}
+
+impl EventFieldDiff {
+    fn push_if_different<T: PartialEq + Debug>(
+        diffs: &mut Vec<EventFieldDiff>,
+        field: &'static str,
+        old: &T,
+        new: &T,
+    ) {
+        if old != new {
+            diffs.push(EventFieldDiff {
+                field,
+                old: format!("{:?}", old),
+                new: format!("{:?}", new),
+            });
+        }
+    }
+}
+
+/// Routing information of an event, without its call stack and data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SCOutputEventHeader {
+    /// when was it generated
+    pub slot: Slot,
+    /// index of the event in the slot
+    pub index_in_slot: u64,
+    /// address that emitted the event
+    pub emitter: Option<Address>,
+    /// address at the origin of the call stack
+    pub origin: Option<Address>,
+    /// block id if there was a block at that slot
+    pub block: Option<BlockId>,
+    /// if the event was generated during a read only execution
+    pub read_only: bool,
+}
+
+impl From<&SCOutputEvent> for SCOutputEventHeader {
+    fn from(event: &SCOutputEvent) -> Self {
+        SCOutputEventHeader {
+            slot: event.context.slot,
+            index_in_slot: event.context.index_in_slot,
+            emitter: event.emitter().copied(),
+            origin: event.origin().copied(),
+            block: event.context.block,
+            read_only: event.context.read_only,
+        }
+    }
+}
+
 /// Context of the event (not generated by the user)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventExecutionContext {
@@ -69,3 +229,127 @@ impl Display for EventExecutionContext {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{SCAddress, SCAddressV0, UserAddress, UserAddressV0};
+
+    fn make_test_event(slot: Slot, index_in_slot: u64, data: &str) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_event_header() {
+        let user = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            Hash::compute_from("user".as_bytes()),
+        )));
+        let sc = Address::SC(SCAddress::SCAddressV0(SCAddressV0(Hash::compute_from(
+            "sc".as_bytes(),
+        ))));
+        let block = BlockId::generate_from_hash(Hash::compute_from("block".as_bytes()));
+        let mut event = make_test_event(Slot::new(12, 3), 4, "data");
+        event.context.block = Some(block);
+        event.context.read_only = true;
+        event.context.call_stack = vec![user, sc].into();
+
+        let header = SCOutputEventHeader::from(&event);
+        assert_eq!(header.slot, Slot::new(12, 3));
+        assert_eq!(header.index_in_slot, 4);
+        assert_eq!(header.emitter, Some(sc));
+        assert_eq!(header.origin, Some(user));
+        assert_eq!(header.block, Some(block));
+        assert!(header.read_only);
+    }
+
+    #[test]
+    fn test_event_shard() {
+        let num_shards = 4;
+        let mut shard_sizes = vec![0; num_shards as usize];
+        for period in 0..100 {
+            for index_in_slot in 0..10 {
+                let event = make_test_event(
+                    Slot::new(period, (index_in_slot % 2) as u8),
+                    index_in_slot,
+                    "",
+                );
+                let shard = event.shard(num_shards);
+                assert!(shard < num_shards);
+                // the shard only depends on the slot and index in slot
+                let mut other = event.clone();
+                other.data = "other data".to_string();
+                assert_eq!(other.shard(num_shards), shard);
+                shard_sizes[shard as usize] += 1;
+            }
+        }
+        // 1000 events over 4 shards: expect roughly 250 events per shard
+        for size in shard_sizes {
+            assert!((150..350).contains(&size), "unbalanced shard: {}", size);
+        }
+    }
+
+    #[test]
+    fn test_event_method_hint() {
+        let mut event =
+            make_test_event(Slot::new(1, 0), 0, "Transfer:{\"from\":\"a\",\"to\":\"b\"}");
+        assert_eq!(event.method_hint(), Some("Transfer"));
+
+        event.data = "{\"from\":\"a\",\"to\":\"b\"}".to_string();
+        assert_eq!(event.method_hint(), None);
+
+        event.data = "no method here".to_string();
+        assert_eq!(event.method_hint(), None);
+
+        event.data = String::new();
+        assert_eq!(event.method_hint(), None);
+    }
+
+    #[test]
+    fn test_event_empty_data() {
+        let event = make_test_event(Slot::new(1, 0), 0, "");
+        let json = serde_json::to_string(&event).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["data"], "");
+
+        let decoded: SCOutputEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.data, "");
+        assert!(event.diff(&decoded).is_empty());
+    }
+
+    #[test]
+    fn test_event_diff() {
+        let event = make_test_event(Slot::new(1, 0), 0, "data");
+        assert!(event.diff(&event.clone()).is_empty());
+
+        let mut other = event.clone();
+        other.context.index_in_slot = 1;
+        other.data = "other data".to_string();
+        assert_eq!(
+            event.diff(&other),
+            vec![
+                EventFieldDiff {
+                    field: "index_in_slot",
+                    old: "0".to_string(),
+                    new: "1".to_string(),
+                },
+                EventFieldDiff {
+                    field: "data",
+                    old: "\"data\"".to_string(),
+                    new: "\"other data\"".to_string(),
+                },
+            ]
+        );
+    }
+}
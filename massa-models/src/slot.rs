@@ -1,10 +1,12 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::error::ModelsError;
+use crate::timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp};
 use massa_hash::Hash;
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
+use massa_time::MassaTime;
 use nom::error::{context, ContextError, ParseError};
 use serde::{Deserialize, Serialize};
 use std::ops::{Bound, RangeBounds};
@@ -190,14 +192,32 @@ impl Slot {
         Ok(Slot { period, thread: 0 })
     }
 
-    /// returns the minimal slot
-    pub const fn min() -> Slot {
+    /// returns the genesis slot (period 0, thread 0)
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// assert!(Slot::genesis().is_genesis());
+    /// assert!(!Slot::new(0, 1).is_genesis());
+    /// assert!(!Slot::new(1, 0).is_genesis());
+    /// ```
+    pub const fn genesis() -> Slot {
         Slot {
             period: 0,
             thread: 0,
         }
     }
 
+    /// check if the slot is the genesis slot
+    pub fn is_genesis(&self) -> bool {
+        *self == Slot::genesis()
+    }
+
+    /// returns the minimal slot
+    pub const fn min() -> Slot {
+        Slot::genesis()
+    }
+
     /// returns the maximal slot
     pub const fn max(thread_count: u8) -> Slot {
         Slot {
@@ -270,6 +290,9 @@ impl Slot {
     /// assert_eq!(slot.get_next_slot(5).unwrap(), Slot::new(10, 4));
     /// let slot = Slot::new(10,4);
     /// assert_eq!(slot.get_next_slot(5).unwrap(), Slot::new(11, 0));
+    /// // the period cannot overflow
+    /// assert!(Slot::max(5).get_next_slot(5).is_err());
+    /// assert_eq!(Slot::new(u64::MAX, 3).get_next_slot(5).unwrap(), Slot::max(5));
     /// ```
     pub fn get_next_slot(&self, thread_count: u8) -> Result<Slot, ModelsError> {
         if self.thread.saturating_add(1u8) >= thread_count {
@@ -298,6 +321,7 @@ impl Slot {
     /// assert_eq!(slot.get_prev_slot(5).unwrap(), Slot::new(10, 0));
     /// let slot = Slot::new(10,0);
     /// assert_eq!(slot.get_prev_slot(5).unwrap(), Slot::new(9, 4));
+    /// assert!(Slot::genesis().get_prev_slot(5).is_err());
     /// ```
     pub fn get_prev_slot(&self, thread_count: u8) -> Result<Slot, ModelsError> {
         match self.thread.checked_sub(1u8) {
@@ -328,6 +352,78 @@ impl Slot {
             .ok_or(ModelsError::PeriodOverflowError)?
             .saturating_sub(s.thread as u64))
     }
+
+    /// Counts the number of slots between self and the slot passed in parameter, in any order
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// let a = Slot::new(10, 3);
+    /// let b = Slot::new(12, 1);
+    /// assert_eq!(a.distance(&b, 4).unwrap(), 6);
+    /// assert_eq!(b.distance(&a, 4).unwrap(), 6);
+    /// assert_eq!(a.distance(&a, 4).unwrap(), 0);
+    /// // the slot count cannot overflow
+    /// assert!(Slot::max(4).distance(&Slot::min(), 4).is_err());
+    /// assert!(Slot::min().distance(&Slot::max(4), 4).is_err());
+    /// assert_eq!(Slot::max(1).distance(&Slot::min(), 1).unwrap(), u64::MAX);
+    /// ```
+    pub fn distance(&self, other: &Slot, thread_count: u8) -> Result<u64, ModelsError> {
+        if self >= other {
+            self.slots_since(other, thread_count)
+        } else {
+            other.slots_since(self, thread_count)
+        }
+    }
+
+    /// Returns the start time of the slot, as a unix timestamp in milliseconds
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// // genesis at 1000ms, 16 seconds periods over 32 threads
+    /// assert_eq!(Slot::genesis().to_unix_millis(1_000, 16_000, 32).unwrap(), 1_000);
+    /// assert_eq!(Slot::new(2, 3).to_unix_millis(1_000, 16_000, 32).unwrap(), 34_500);
+    /// ```
+    pub fn to_unix_millis(
+        &self,
+        genesis_timestamp_millis: u64,
+        t0_millis: u64,
+        thread_count: u8,
+    ) -> Result<u64, ModelsError> {
+        Ok(get_block_slot_timestamp(
+            thread_count,
+            MassaTime::from_millis(t0_millis),
+            MassaTime::from_millis(genesis_timestamp_millis),
+            *self,
+        )?
+        .to_millis())
+    }
+
+    /// Returns the latest slot started at the given unix timestamp in milliseconds,
+    /// or `None` if the timestamp is before genesis
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// // genesis at 1000ms, 16 seconds periods over 32 threads
+    /// assert_eq!(Slot::from_unix_millis(1_000, 1_000, 16_000, 32).unwrap(), Some(Slot::genesis()));
+    /// assert_eq!(Slot::from_unix_millis(34_600, 1_000, 16_000, 32).unwrap(), Some(Slot::new(2, 3)));
+    /// assert_eq!(Slot::from_unix_millis(999, 1_000, 16_000, 32).unwrap(), None);
+    /// ```
+    pub fn from_unix_millis(
+        timestamp_millis: u64,
+        genesis_timestamp_millis: u64,
+        t0_millis: u64,
+        thread_count: u8,
+    ) -> Result<Option<Slot>, ModelsError> {
+        get_latest_block_slot_at_timestamp(
+            thread_count,
+            MassaTime::from_millis(t0_millis),
+            MassaTime::from_millis(genesis_timestamp_millis),
+            MassaTime::from_millis(timestamp_millis),
+        )
+    }
 }
 
 /// When an address is drawn to create an endorsement it is selected for a specific index
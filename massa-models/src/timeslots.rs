@@ -169,7 +169,7 @@ pub fn get_closest_slot_to_timestamp(
         match get_latest_block_slot_at_timestamp(thread_count, t0, genesis_timestamp, timestamp)
             .unwrap()
         {
-            None => return Slot::new(0, 0), // we are before genesis
+            None => return Slot::genesis(), // we are before genesis
             Some(s) => s,
         };
 